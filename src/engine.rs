@@ -0,0 +1,219 @@
+// The SHA-256 and SHA-512 engines (see `lib.rs` and `sha512.rs`) are the
+// same algorithm parameterized over word width: same message schedule,
+// same round structure, same streaming buffer logic, just `u32`/64
+// rounds/8 byte length field for one and `u64`/80 rounds/16 byte length
+// field for the other. This macro is the single definition of that
+// algorithm; each engine module instantiates it once for its word type
+// instead of hand-copying the logic (which is how the `update` overwrite
+// bug ended up in both engines at once).
+macro_rules! sha2_engine {
+    (
+        word = $W:ty,
+        word_bytes = $WB:expr,
+        block_bytes = $BB:expr,
+        block_words = $BW:expr,
+        rounds = $R:expr,
+        state_words = $SW:expr,
+        bit_len = $BL:ty,
+        k = $K:expr,
+        compress_fn = $COMPRESS:ident,
+        big_sigma0 = ($bs0a:expr, $bs0b:expr, $bs0c:expr),
+        big_sigma1 = ($bs1a:expr, $bs1b:expr, $bs1c:expr),
+        small_sigma0 = ($ss0a:expr, $ss0b:expr, $ss0c:expr),
+        small_sigma1 = ($ss1a:expr, $ss1b:expr, $ss1c:expr),
+    ) => {
+
+// The six logical functions, sharing the same name as the functions in
+// the specification.
+fn ch(x: $W, y: $W, z: $W) -> $W {
+    (x & y) ^ (!x & z)
+}
+
+fn maj(x: $W, y: $W, z: $W) -> $W {
+    (x & y) ^ (x & z) ^ (y & z)
+}
+
+fn big_sigma_zero(x: $W) -> $W {
+    x.rotate_right($bs0a) ^ x.rotate_right($bs0b) ^ x.rotate_right($bs0c)
+}
+
+fn big_sigma_one(x: $W) -> $W {
+    x.rotate_right($bs1a) ^ x.rotate_right($bs1b) ^ x.rotate_right($bs1c)
+}
+
+fn little_sigma_zero(x: $W) -> $W {
+    x.rotate_right($ss0a) ^ x.rotate_right($ss0b) ^ (x >> $ss0c)
+}
+
+fn little_sigma_one(x: $W) -> $W {
+    x.rotate_right($ss1a) ^ x.rotate_right($ss1b) ^ (x >> $ss1c)
+}
+
+fn write_word_be(dst: &mut [u8], x: $W) {
+    dst.copy_from_slice(&x.to_be_bytes());
+}
+
+fn words_to_bytes(hash: &[$W; $SW]) -> [u8; $SW * $WB] {
+    let mut bytes = [0u8; $SW * $WB];
+
+    for (i, word) in hash.iter().enumerate() {
+        write_word_be(&mut bytes[i * $WB..i * $WB + $WB], *word);
+    }
+
+    bytes
+}
+
+// Reassemble a block-sized byte buffer into the big endian words that
+// the compression function operates on.
+fn bytes_to_block(bytes: &[u8; $BB]) -> [$W; $BW] {
+    let mut block = [0 as $W; $BW];
+
+    for (i, word) in block.iter_mut().enumerate() {
+        *word = <$W>::from_be_bytes(bytes[i * $WB..i * $WB + $WB].try_into().unwrap());
+    }
+
+    block
+}
+
+fn $COMPRESS(hash: &mut [$W; $SW], block: &[$W; $BW]) {
+    // Allow for integer wrapping
+    use std::num::Wrapping;
+
+    // Message schedule
+    let mut w = [Wrapping(0 as $W); $R];
+
+    let (mut a, mut b, mut c, mut d,
+         mut e, mut f, mut g, mut h) = (
+             hash[0], hash[1], hash[2], hash[3],
+             hash[4], hash[5], hash[6], hash[7]
+                                       );
+
+    for i in 0..$R {
+        if i < $BW {
+            w[i] = Wrapping(block[i]);
+        } else {
+            w[i] = Wrapping(little_sigma_one(w[i-2].0)) + w[i-7]
+                 + Wrapping(little_sigma_zero(w[i-15].0)) + w[i-16];
+        }
+
+        let t_one = Wrapping(h) + Wrapping(big_sigma_one(e))
+              + Wrapping(ch(e, f, g)) + Wrapping($K[i])
+              + w[i];
+
+        let t_two = Wrapping(big_sigma_zero(a)) + Wrapping(maj(a, b, c));
+
+        h = g;
+        g = f;
+        f = e;
+        e = (Wrapping(d) + t_one).0;
+        d = c;
+        c = b;
+        b = a;
+        a = (t_one + t_two).0;
+    }
+
+    hash[0] = (Wrapping(hash[0]) + Wrapping(a)).0;
+    hash[1] = (Wrapping(hash[1]) + Wrapping(b)).0;
+    hash[2] = (Wrapping(hash[2]) + Wrapping(c)).0;
+    hash[3] = (Wrapping(hash[3]) + Wrapping(d)).0;
+    hash[4] = (Wrapping(hash[4]) + Wrapping(e)).0;
+    hash[5] = (Wrapping(hash[5]) + Wrapping(f)).0;
+    hash[6] = (Wrapping(hash[6]) + Wrapping(g)).0;
+    hash[7] = (Wrapping(hash[7]) + Wrapping(h)).0;
+}
+
+// Incremental hasher shared by every variant built on this engine. Holds
+// the running hash state, a block-sized buffer for bytes that don't yet
+// fill a full block, and a running bit count, so a large message (e.g.
+// a file) can be hashed in fixed size chunks instead of being loaded
+// into memory all at once.
+pub struct Context {
+    hash: [$W; $SW],
+    buffer: [u8; $BB],
+    buffer_len: usize,
+    bit_len: $BL,
+}
+
+impl Context {
+    fn with_iv(iv: [$W; $SW]) -> Context {
+        Context {
+            hash: iv,
+            buffer: [0; $BB],
+            buffer_len: 0,
+            bit_len: 0,
+        }
+    }
+
+    // Feed more message bytes into the hash. Complete blocks are run
+    // through the compression function immediately; any remainder is
+    // kept in `buffer` until the next call (or `finalize`) completes it.
+    pub fn update(&mut self, mut data: &[u8]) -> Result<(), $crate::HashError> {
+        let added_bits = data.len() as $BL * 8;
+        self.bit_len = self.bit_len.checked_add(added_bits)
+            .ok_or($crate::HashError::DataTooLarge)?;
+
+        if self.buffer_len > 0 {
+            let needed = $BB - self.buffer_len;
+            let take = std::cmp::min(needed, data.len());
+
+            self.buffer[self.buffer_len..self.buffer_len + take]
+                .copy_from_slice(&data[..take]);
+            self.buffer_len += take;
+            data = &data[take..];
+
+            if self.buffer_len == $BB {
+                $COMPRESS(&mut self.hash, &bytes_to_block(&self.buffer));
+                self.buffer_len = 0;
+            }
+        }
+
+        while data.len() >= $BB {
+            let block = bytes_to_block(data[..$BB].try_into().unwrap());
+            $COMPRESS(&mut self.hash, &block);
+            data = &data[$BB..];
+        }
+
+        self.buffer[self.buffer_len..self.buffer_len + data.len()]
+            .copy_from_slice(data);
+        self.buffer_len += data.len();
+
+        Ok(())
+    }
+
+    // Apply the '1' bit and the big endian bit length to the trailing
+    // partial block (spilling into a second block if there isn't room
+    // for the length alongside it), then fold it through the
+    // compression function to produce the finished hash. Shared by
+    // every variant of this engine; only the number of result words
+    // each variant keeps differs.
+    fn finalize_words(mut self) -> [$W; $SW] {
+        let bit_len = self.bit_len;
+        let mut pos = self.buffer_len;
+        let len_start = $BB - 2 * $WB;
+
+        self.buffer[pos] = 0x80;
+        pos += 1;
+
+        if pos > len_start {
+            for byte in &mut self.buffer[pos..] {
+                *byte = 0;
+            }
+            $COMPRESS(&mut self.hash, &bytes_to_block(&self.buffer));
+            self.buffer = [0; $BB];
+        } else {
+            for byte in &mut self.buffer[pos..len_start] {
+                *byte = 0;
+            }
+        }
+
+        self.buffer[len_start..$BB].copy_from_slice(&bit_len.to_be_bytes());
+        $COMPRESS(&mut self.hash, &bytes_to_block(&self.buffer));
+
+        self.hash
+    }
+}
+
+    };
+}
+
+pub(crate) use sha2_engine;
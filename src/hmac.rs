@@ -0,0 +1,42 @@
+// HMAC-SHA256, per NIST FIPS 198-1 / RFC 2104, built on top of the
+// streaming `Context` so that `msg` is hashed in place rather than
+// being concatenated into a fresh buffer first.
+
+use crate::{HashError, Sha256};
+
+const BLOCK_SIZE: usize = 64;
+const IPAD: u8 = 0x36;
+const OPAD: u8 = 0x5c;
+
+// Keyed message authentication: `H(opad || H(ipad || msg))`. Keys
+// longer than the 64 byte block size are first hashed down to 32
+// bytes; shorter keys are right-padded with zeroes.
+pub fn hmac_sha256(key: &[u8], msg: &[u8]) -> Result<[u8; 32], HashError> {
+    let mut key_block = [0u8; BLOCK_SIZE];
+
+    if key.len() > BLOCK_SIZE {
+        let hashed_key = Sha256::new(key)?;
+        key_block[..32].copy_from_slice(&hashed_key.as_bytes());
+    } else {
+        key_block[..key.len()].copy_from_slice(key);
+    }
+
+    let mut ipad = [0u8; BLOCK_SIZE];
+    let mut opad = [0u8; BLOCK_SIZE];
+
+    for i in 0..BLOCK_SIZE {
+        ipad[i] = key_block[i] ^ IPAD;
+        opad[i] = key_block[i] ^ OPAD;
+    }
+
+    let mut inner = Sha256::context();
+    inner.update(&ipad)?;
+    inner.update(msg)?;
+    let inner_hash = inner.finalize();
+
+    let mut outer = Sha256::context();
+    outer.update(&opad)?;
+    outer.update(&inner_hash.as_bytes())?;
+
+    Ok(outer.finalize().as_bytes())
+}
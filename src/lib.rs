@@ -1,10 +1,25 @@
-// SHA-256 Function implemented pursuant to NIST FIPS 180-4
+// SHA-2 family implemented pursuant to NIST FIPS 180-4
 // which is available at https://doi.org/10.6028/NIST.FIPS.180-4
+//
+// SHA-256 and SHA-224 share the 32-bit engine below. SHA-512, SHA-384,
+// and SHA-512/256 share a parallel 64-bit engine in `sha512`.
 
 // Unit tests
 #[cfg(test)]
 mod tests;
 
+mod engine;
+
+mod sha512;
+pub use sha512::{Sha384, Sha512, Sha512Trunc256};
+
+mod hmac;
+pub use hmac::hmac_sha256;
+
+mod x86;
+
+use engine::sha2_engine;
+
 // Constants to be fed into every round
 const SHA256_CONST: [u32; 64] = [
     0x428a2f98,0x71374491,0xb5c0fbcf,0xe9b5dba5,0x3956c25b,0x59f111f1,0x923f82a4,0xab1c5ed5,
@@ -22,192 +37,199 @@ pub enum HashError {
     DataTooLarge
 }
 
+// Initial hash values, as specified in FIPS 180-4 section 5.3.3
+const INITIAL_HASH: [u32; 8] = [
+    0x6a09e667,
+    0xbb67ae85,
+    0x3c6ef372,
+    0xa54ff53a,
+    0x510e527f,
+    0x9b05688c,
+    0x1f83d9ab,
+    0x5be0cd19,
+];
+
+sha2_engine! {
+    word = u32,
+    word_bytes = 4,
+    block_bytes = 64,
+    block_words = 16,
+    rounds = 64,
+    state_words = 8,
+    bit_len = u64,
+    k = SHA256_CONST,
+    compress_fn = block_hash_scalar,
+    big_sigma0 = (2, 13, 22),
+    big_sigma1 = (6, 11, 25),
+    small_sigma0 = (7, 18, 3),
+    small_sigma1 = (17, 19, 10),
+}
+
+impl Context {
+    fn new() -> Context {
+        Context::with_iv(INITIAL_HASH)
+    }
+}
+
 // Convenience struct encapsulating raw hash value that can convert hash to hex string
 pub struct Sha256 {
-    hash: [u32; 8]
+    hash: [u8; 32]
 }
 
 impl Sha256 {
     pub fn new(inp: &[u8]) -> Result<Sha256, HashError> {
-        Ok(Sha256 {
-            hash: sha256(inp)?
-        })
-    }
-
-    pub fn to_string(&self) -> String {
-        format!("{:08x}{:08x}{:08x}{:08x}\
-                 {:08x}{:08x}{:08x}{:08x}", self.hash[0],
-                                            self.hash[1],
-                                            self.hash[2],
-                                            self.hash[3],
-                                            self.hash[4],
-                                            self.hash[5],
-                                            self.hash[6],
-                                            self.hash[7])
+        let mut ctx = Sha256::context();
+        ctx.update(inp)?;
+        Ok(ctx.finalize())
     }
-}
 
-fn sha256(inp: &[u8]) -> Result<[u32; 8], HashError> {
-    if inp.len() > 1 << 58 {
-        return Err(HashError::DataTooLarge);
+    // Start an incremental hash, allowing the message to be fed in
+    // piece by piece instead of all at once
+    pub fn context() -> Context {
+        Context::new()
     }
 
-    let blocks = pad_data(inp);
+    // The raw 32 byte big endian digest, for callers that want bytes
+    // instead of a hex string (signature verification, Merkle trees,
+    // comparing against other libraries' byte output).
+    pub fn as_bytes(&self) -> [u8; 32] {
+        self.hash
+    }
 
-    // Initial Hash values
-    let mut hash: [u32; 8] = [
-        0x6a09e667,
-        0xbb67ae85,
-        0x3c6ef372,
-        0xa54ff53a,
-        0x510e527f,
-        0x9b05688c,
-        0x1f83d9ab,
-        0x5be0cd19,
-    ];
+    // The initial hash value new contexts start from, for callers
+    // driving `compress` directly instead of going through `new`.
+    pub fn initial_state() -> [u32; 8] {
+        INITIAL_HASH
+    }
 
-    for block in blocks.iter() {
-        block_hash(&mut hash, block);
+    // Apply a single 512-bit block to an 8-word state. This is the
+    // raw Merkle-Damgard compression step that `update` drives one
+    // block at a time; exposed on its own for constraint systems,
+    // length-extension research, and custom constructions built
+    // directly on top of SHA-256's compression function.
+    pub fn compress(state: &mut [u32; 8], block: &[u32; 16]) {
+        block_hash(state, block);
     }
+}
 
-    Ok(hash)
+impl AsRef<[u8]> for Sha256 {
+    fn as_ref(&self) -> &[u8] {
+        &self.hash
+    }
 }
 
-// Pad data into 512 bit blocks.
-// A '1' bit is put in the next bytes after
-// the input data, and then the last two u32 values
-// represent a 64 bit unsigned number which is the size
-// of the input data
-fn pad_data(inp: &[u8]) -> Vec<[u32; 16]> {
-    let len_inp_bits = inp.len() * 8;
-    
-    // Calculate how many 512 bit blocks are needed for the data itself.
-    // Then, if the data occupies bit 448 or greater in the last block,
-    // allocate another block so that the '1' bit and the 64 bit size signature
-    // fit in.
-    // Lastly, if the input size is zero, then create only one block.
-    let num_blocks = std::cmp::max(((len_inp_bits as f32 / 512_f32).ceil()
-                          +((len_inp_bits % 512) as f32 / 448_f32).floor()) as usize, 1);
-
-    let mut blocks = vec![[0_u32; 16]; num_blocks];
-
-    // Keep track outside of position outside of the for loop to make inserting
-    // the '1' bit easier
-    let mut block_num = 0;
-    let mut block_pos = 0;
-
-    for (i, x) in inp.iter().enumerate() {
-        // Could also be represented as '((i * 8) as f32 / 512_f32)'
-        // However, the representation below removes the need to multiply by 8
-        block_num = (i as f32 / 64_f32).floor() as usize;
-        block_pos = ((i % 64) as f32 / 4_f32).floor() as usize;
-
-        // Big endian implementation.
-        // Fit four u8 values into one u32 value, going from left to right.
-        blocks[block_num][block_pos] |= (*x as u32) << 24 - (i % 4 * 8);
-    }
-
-    // Determine what part of the u32 the '1' bit will fit into
-    let final_u32 = inp.len() % 4;
-
-    // If the '1' bit will be inserted into a new u32 element that
-    // is not at the beginning of the array, then adjust the indices
-    // accordingly.
-    if final_u32 == 0 && len_inp_bits != 0 {
-        if block_pos == 15 {
-            block_num += 1;
-            block_pos = 0;
-        } else {
-            block_pos += 1;
+impl std::fmt::LowerHex for Sha256 {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for byte in self.hash.iter() {
+            write!(f, "{:02x}", byte)?;
         }
+        Ok(())
     }
+}
 
-    // Set the most significant bit to 1, which is nearest to the end of the input
-    // data, as per specification.
-    blocks[block_num][block_pos] |= (128 as u32) << 24 - (final_u32 * 8);
+impl std::fmt::Display for Sha256 {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{:x}", self)
+    }
+}
 
-    // Set the size signature
-    blocks[num_blocks - 1][15] = len_inp_bits as u32;
-    blocks[num_blocks - 1][14] = (len_inp_bits >> 32) as u32;
+// `Context` (hash state, 64 byte buffer, running bit count, `update`,
+// `finalize_words`) is generated by the `sha2_engine!` invocation above,
+// shared with SHA-224.
+impl Context {
+    pub fn finalize(self) -> Sha256 {
+        Sha256 { hash: words_to_bytes(&self.finalize_words()) }
+    }
 
-    blocks
-}
+    pub fn finalize_224(self) -> Sha224 {
+        let words = self.finalize_words();
+        let mut hash = [0u8; 28];
 
-fn block_hash(hash: &mut [u32; 8], block: &[u32; 16]) {
-    // Allow for integer wrapping
-    use std::num::Wrapping;
-
-    // Message schedule
-    let mut w = [Wrapping(0u32); 64];
-
-    // The 8 working variables that are modified per round
-    // TODO: Using an array is less idiomatic in respect to the NIST document,
-    // but more idiomatic in respect to programming.
-    let (mut a, mut b, mut c, mut d,
-         mut e, mut f, mut g, mut h) = (
-             hash[0], hash[1], hash[2], hash[3],
-             hash[4], hash[5], hash[6], hash[7]
-                                       );
-
-    // 64 rounds to be executed per block
-    for i in 0..64 {
-        if i < 16 {
-            w[i] = Wrapping(block[i]);
-        } else {
-            w[i] = Wrapping(little_sigma_one(w[i-2].0)) + w[i-7]
-                 + Wrapping(little_sigma_zero(w[i-15].0)) + w[i-16];
+        for (i, word) in words[..7].iter().enumerate() {
+            write_word_be(&mut hash[i * 4..i * 4 + 4], *word);
         }
-        
-        let t_one = Wrapping(h) + Wrapping(big_sigma_one(e))
-              + Wrapping(ch(e, f, g)) + Wrapping(SHA256_CONST[i])
-              + w[i];
-
-        let t_two = Wrapping(big_sigma_zero(a)) + Wrapping(maj(a, b, c));
-
-        h = g;
-        g = f;
-        f = e;
-        e = (Wrapping(d) + t_one).0;
-        d = c;
-        c = b;
-        b = a;
-        a = (t_one + t_two).0;
-    }
-
-    //TODO: Get rid of all the repetition
-    hash[0] = (Wrapping(hash[0]) + Wrapping(a)).0;
-    hash[1] = (Wrapping(hash[1]) + Wrapping(b)).0;
-    hash[2] = (Wrapping(hash[2]) + Wrapping(c)).0;
-    hash[3] = (Wrapping(hash[3]) + Wrapping(d)).0;
-    hash[4] = (Wrapping(hash[4]) + Wrapping(e)).0;
-    hash[5] = (Wrapping(hash[5]) + Wrapping(f)).0;
-    hash[6] = (Wrapping(hash[6]) + Wrapping(g)).0;
-    hash[7] = (Wrapping(hash[7]) + Wrapping(h)).0;
+
+        Sha224 { hash }
+    }
 }
 
-// The six logical functions, 
-// sharing the same name as the functions in the specification.
+// SHA-224 initial hash values, as specified in FIPS 180-4 section 5.3.2.
+// SHA-224 is otherwise identical to SHA-256: same 64 rounds, same
+// constants, same block_hash; only the IV differs and the last of the
+// eight result words is dropped from the output.
+const INITIAL_HASH_224: [u32; 8] = [
+    0xc1059ed8,
+    0x367cd507,
+    0x3070dd17,
+    0xf70e5939,
+    0xffc00b31,
+    0x68581511,
+    0x64f98fa7,
+    0xbefa4fa4,
+];
 
-fn ch(x: u32, y: u32, z: u32) -> u32 {
-    (x & y) ^ (!x & z)
+// SHA-224 digest, truncated to the first 7 of the 8 words produced by
+// the shared 256-bit engine.
+pub struct Sha224 {
+    hash: [u8; 28]
 }
 
-fn maj(x: u32, y: u32, z: u32) -> u32 {
-    (x & y) ^ (x & z) ^ (y & z)
+impl Sha224 {
+    pub fn new(inp: &[u8]) -> Result<Sha224, HashError> {
+        let mut ctx = Sha224::context();
+        ctx.update(inp)?;
+        Ok(ctx.finalize_224())
+    }
+
+    // Start an incremental SHA-224 hash. Shares `Context`'s `update`
+    // with SHA-256; only the initial hash value and the finishing step
+    // differ.
+    pub fn context() -> Context {
+        Context::with_iv(INITIAL_HASH_224)
+    }
+
+    pub fn as_bytes(&self) -> [u8; 28] {
+        self.hash
+    }
 }
 
-fn big_sigma_zero(x: u32) -> u32 {
-    x.rotate_right(2) ^ x.rotate_right(13) ^ x.rotate_right(22)
+impl AsRef<[u8]> for Sha224 {
+    fn as_ref(&self) -> &[u8] {
+        &self.hash
+    }
 }
 
-fn big_sigma_one(x: u32) -> u32 {
-    x.rotate_right(6) ^ x.rotate_right(11) ^ x.rotate_right(25)
+impl std::fmt::LowerHex for Sha224 {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for byte in self.hash.iter() {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
 }
 
-fn little_sigma_zero(x: u32) -> u32 {
-    x.rotate_right(7) ^ x.rotate_right(18) ^ (x >> 3)
+impl std::fmt::Display for Sha224 {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{:x}", self)
+    }
 }
 
-fn little_sigma_one(x: u32) -> u32 {
-    x.rotate_right(17) ^ x.rotate_right(19) ^ (x >> 10)
+// Dispatches to the SHA-NI accelerated backend when the running CPU
+// supports it, falling back to the portable scalar loop otherwise. The
+// public API (and the rest of this crate) is unaffected either way --
+// this is purely a performance redesign of the inner compression loop.
+fn block_hash(hash: &mut [u32; 8], block: &[u32; 16]) {
+    #[cfg(target_arch = "x86_64")]
+    {
+        if x86::available() {
+            unsafe { x86::compress(hash, block) };
+            return;
+        }
+    }
+
+    block_hash_scalar(hash, block);
 }
+
+// `block_hash_scalar`, `ch`/`maj`/the sigma functions, and the
+// big-endian conversion helpers are generated by the `sha2_engine!`
+// invocation above.
@@ -0,0 +1,246 @@
+// SHA-512 family: SHA-512, SHA-384, and SHA-512/256. These share a
+// 64-bit, 80-round, 1024-bit-block engine that mirrors the 32-bit
+// SHA-256 engine in `lib.rs` one level up (message schedule, working
+// variables, round constants), just built on `u64` instead of `u32`.
+// SHA-384 and SHA-512/256 reuse the SHA-512 engine unchanged and only
+// differ in their initial hash value and how many result words they
+// keep.
+
+use crate::HashError;
+use crate::engine::sha2_engine;
+
+// Constants to be fed into every round
+const SHA512_CONST: [u64; 80] = [
+    0x428a2f98d728ae22, 0x7137449123ef65cd, 0xb5c0fbcfec4d3b2f, 0xe9b5dba58189dbbc,
+    0x3956c25bf348b538, 0x59f111f1b605d019, 0x923f82a4af194f9b, 0xab1c5ed5da6d8118,
+    0xd807aa98a3030242, 0x12835b0145706fbe, 0x243185be4ee4b28c, 0x550c7dc3d5ffb4e2,
+    0x72be5d74f27b896f, 0x80deb1fe3b1696b1, 0x9bdc06a725c71235, 0xc19bf174cf692694,
+    0xe49b69c19ef14ad2, 0xefbe4786384f25e3, 0x0fc19dc68b8cd5b5, 0x240ca1cc77ac9c65,
+    0x2de92c6f592b0275, 0x4a7484aa6ea6e483, 0x5cb0a9dcbd41fbd4, 0x76f988da831153b5,
+    0x983e5152ee66dfab, 0xa831c66d2db43210, 0xb00327c898fb213f, 0xbf597fc7beef0ee4,
+    0xc6e00bf33da88fc2, 0xd5a79147930aa725, 0x06ca6351e003826f, 0x142929670a0e6e70,
+    0x27b70a8546d22ffc, 0x2e1b21385c26c926, 0x4d2c6dfc5ac42aed, 0x53380d139d95b3df,
+    0x650a73548baf63de, 0x766a0abb3c77b2a8, 0x81c2c92e47edaee6, 0x92722c851482353b,
+    0xa2bfe8a14cf10364, 0xa81a664bbc423001, 0xc24b8b70d0f89791, 0xc76c51a30654be30,
+    0xd192e819d6ef5218, 0xd69906245565a910, 0xf40e35855771202a, 0x106aa07032bbd1b8,
+    0x19a4c116b8d2d0c8, 0x1e376c085141ab53, 0x2748774cdf8eeb99, 0x34b0bcb5e19b48a8,
+    0x391c0cb3c5c95a63, 0x4ed8aa4ae3418acb, 0x5b9cca4f7763e373, 0x682e6ff3d6b2b8a3,
+    0x748f82ee5defb2fc, 0x78a5636f43172f60, 0x84c87814a1f0ab72, 0x8cc702081a6439ec,
+    0x90befffa23631e28, 0xa4506cebde82bde9, 0xbef9a3f7b2c67915, 0xc67178f2e372532b,
+    0xca273eceea26619c, 0xd186b8c721c0c207, 0xeada7dd6cde0eb1e, 0xf57d4f7fee6ed178,
+    0x06f067aa72176fba, 0x0a637dc5a2c898a6, 0x113f9804bef90dae, 0x1b710b35131c471b,
+    0x28db77f523047d84, 0x32caab7b40c72493, 0x3c9ebe0a15c9bebc, 0x431d67c49c100d4c,
+    0x4cc5d4becb3e42b6, 0x597f299cfc657e2a, 0x5fcb6fab3ad6faec, 0x6c44198c4a475817,
+];
+
+// SHA-512 initial hash values
+const INITIAL_HASH_512: [u64; 8] = [
+    0x6a09e667f3bcc908,
+    0xbb67ae8584caa73b,
+    0x3c6ef372fe94f82b,
+    0xa54ff53a5f1d36f1,
+    0x510e527fade682d1,
+    0x9b05688c2b3e6c1f,
+    0x1f83d9abfb41bd6b,
+    0x5be0cd19137e2179,
+];
+
+// SHA-384 initial hash values
+const INITIAL_HASH_384: [u64; 8] = [
+    0xcbbb9d5dc1059ed8,
+    0x629a292a367cd507,
+    0x9159015a3070dd17,
+    0x152fecd8f70e5939,
+    0x67332667ffc00b31,
+    0x8eb44a8768581511,
+    0xdb0c2e0d64f98fa7,
+    0x47b5481dbefa4fa4,
+];
+
+// SHA-512/256 initial hash values
+const INITIAL_HASH_512_256: [u64; 8] = [
+    0x22312194fc2bf72c,
+    0x9f555fa3c84c64c2,
+    0x2393b86b6f53b151,
+    0x963877195940eabd,
+    0x96283ee2a88effe3,
+    0xbe5e1e2553863992,
+    0x2b0199fc2c85b8aa,
+    0x0eb72ddc81c52ca2,
+];
+
+
+// SHA-512 digest
+pub struct Sha512 {
+    hash: [u8; 64]
+}
+
+impl Sha512 {
+    pub fn new(inp: &[u8]) -> Result<Sha512, HashError> {
+        let mut ctx = Sha512::context();
+        ctx.update(inp)?;
+        Ok(ctx.finalize())
+    }
+
+    pub fn context() -> Context {
+        Context::with_iv(INITIAL_HASH_512)
+    }
+
+    pub fn as_bytes(&self) -> [u8; 64] {
+        self.hash
+    }
+}
+
+impl AsRef<[u8]> for Sha512 {
+    fn as_ref(&self) -> &[u8] {
+        &self.hash
+    }
+}
+
+impl std::fmt::LowerHex for Sha512 {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for byte in self.hash.iter() {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for Sha512 {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{:x}", self)
+    }
+}
+
+// SHA-384 digest: the SHA-512 engine, truncated to the first 6 of the
+// 8 result words.
+pub struct Sha384 {
+    hash: [u8; 48]
+}
+
+impl Sha384 {
+    pub fn new(inp: &[u8]) -> Result<Sha384, HashError> {
+        let mut ctx = Sha384::context();
+        ctx.update(inp)?;
+        Ok(ctx.finalize_384())
+    }
+
+    pub fn context() -> Context {
+        Context::with_iv(INITIAL_HASH_384)
+    }
+
+    pub fn as_bytes(&self) -> [u8; 48] {
+        self.hash
+    }
+}
+
+impl AsRef<[u8]> for Sha384 {
+    fn as_ref(&self) -> &[u8] {
+        &self.hash
+    }
+}
+
+impl std::fmt::LowerHex for Sha384 {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for byte in self.hash.iter() {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for Sha384 {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{:x}", self)
+    }
+}
+
+// SHA-512/256 digest: the SHA-512 engine with its own IV, truncated to
+// the first 4 of the 8 result words.
+pub struct Sha512Trunc256 {
+    hash: [u8; 32]
+}
+
+impl Sha512Trunc256 {
+    pub fn new(inp: &[u8]) -> Result<Sha512Trunc256, HashError> {
+        let mut ctx = Sha512Trunc256::context();
+        ctx.update(inp)?;
+        Ok(ctx.finalize_512_256())
+    }
+
+    pub fn context() -> Context {
+        Context::with_iv(INITIAL_HASH_512_256)
+    }
+
+    pub fn as_bytes(&self) -> [u8; 32] {
+        self.hash
+    }
+}
+
+impl AsRef<[u8]> for Sha512Trunc256 {
+    fn as_ref(&self) -> &[u8] {
+        &self.hash
+    }
+}
+
+impl std::fmt::LowerHex for Sha512Trunc256 {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for byte in self.hash.iter() {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for Sha512Trunc256 {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{:x}", self)
+    }
+}
+
+sha2_engine! {
+    word = u64,
+    word_bytes = 8,
+    block_bytes = 128,
+    block_words = 16,
+    rounds = 80,
+    state_words = 8,
+    bit_len = u128,
+    k = SHA512_CONST,
+    compress_fn = block_hash,
+    big_sigma0 = (28, 34, 39),
+    big_sigma1 = (14, 18, 41),
+    small_sigma0 = (1, 8, 7),
+    small_sigma1 = (19, 61, 6),
+}
+
+// `Context` (hash state, 128 byte buffer, 128 bit running length,
+// `update`, `finalize_words`) is generated by the `sha2_engine!`
+// invocation above, mirroring `crate::Context` one level up.
+impl Context {
+    pub fn finalize(self) -> Sha512 {
+        Sha512 { hash: words_to_bytes(&self.finalize_words()) }
+    }
+
+    pub fn finalize_384(self) -> Sha384 {
+        let words = self.finalize_words();
+        let mut hash = [0u8; 48];
+
+        for (i, word) in words[..6].iter().enumerate() {
+            write_word_be(&mut hash[i * 8..i * 8 + 8], *word);
+        }
+
+        Sha384 { hash }
+    }
+
+    pub fn finalize_512_256(self) -> Sha512Trunc256 {
+        let words = self.finalize_words();
+        let mut hash = [0u8; 32];
+
+        for (i, word) in words[..4].iter().enumerate() {
+            write_word_be(&mut hash[i * 8..i * 8 + 8], *word);
+        }
+
+        Sha512Trunc256 { hash }
+    }
+}
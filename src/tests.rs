@@ -10,13 +10,6 @@ fn compare_hash(inp: &[u8], other_hash: &str) {
     }
 }
 
-#[test]
-fn pad_data_none() {
-    let blocks = pad_data(b"");
-    assert_eq!(vec![[0b10000000000000000000000000000000, 0, 0, 0, 0, 0, 0, 0,
-                 0, 0, 0, 0, 0, 0, 0, 0]], blocks);
-}
-
 #[test]
 fn hash_none() {
     compare_hash(b"", "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855");
@@ -27,6 +20,266 @@ fn hash_string() {
     compare_hash(b"Hello, world!", "315f5bdb76d078c43b8ac0064e4a0164612b1fce77c869345bfc94c75894edd3");
 }
 
+// `update` must append successive chunks to its internal buffer rather
+// than overwrite it, even when no individual chunk fills a block.
+#[test]
+fn update_appends_across_calls() {
+    let mut ctx = Sha256::context();
+    match ctx.update(b"He") {
+        Ok(()) => (),
+        Err(HashError::DataTooLarge) => panic!("Error: input size greater than 2^64 bits large.")
+    }
+    match ctx.update(b"llo, world!") {
+        Ok(()) => (),
+        Err(HashError::DataTooLarge) => panic!("Error: input size greater than 2^64 bits large.")
+    }
+
+    assert_eq!(ctx.finalize().to_string(), "315f5bdb76d078c43b8ac0064e4a0164612b1fce77c869345bfc94c75894edd3");
+}
+
+fn compare_hash_224(inp: &[u8], other_hash: &str) {
+    let hash = Sha224::new(inp);
+    match hash {
+        Ok(res) => assert_eq!(res.to_string(), other_hash),
+        Err(err) => match err {
+            HashError::DataTooLarge => panic!("Error: input size greater than 2^64 bits large.")
+        }
+    }
+}
+
+fn compare_hash_384(inp: &[u8], other_hash: &str) {
+    let hash = Sha384::new(inp);
+    match hash {
+        Ok(res) => assert_eq!(res.to_string(), other_hash),
+        Err(err) => match err {
+            HashError::DataTooLarge => panic!("Error: input size greater than 2^128 bits large.")
+        }
+    }
+}
+
+fn compare_hash_512(inp: &[u8], other_hash: &str) {
+    let hash = Sha512::new(inp);
+    match hash {
+        Ok(res) => assert_eq!(res.to_string(), other_hash),
+        Err(err) => match err {
+            HashError::DataTooLarge => panic!("Error: input size greater than 2^128 bits large.")
+        }
+    }
+}
+
+fn compare_hash_512_256(inp: &[u8], other_hash: &str) {
+    let hash = Sha512Trunc256::new(inp);
+    match hash {
+        Ok(res) => assert_eq!(res.to_string(), other_hash),
+        Err(err) => match err {
+            HashError::DataTooLarge => panic!("Error: input size greater than 2^128 bits large.")
+        }
+    }
+}
+
+#[test]
+fn hash_224_none() {
+    compare_hash_224(b"", "d14a028c2a3a2bc9476102bb288234c415a2b01f828ea62ac5b3e42f");
+}
+
+#[test]
+fn hash_224_string() {
+    compare_hash_224(b"Hello, world!", "8552d8b7a7dc5476cb9e25dee69a8091290764b7f2a64fe6e78e9568");
+}
+
+#[test]
+fn hash_384_none() {
+    compare_hash_384(b"", "38b060a751ac96384cd9327eb1b1e36a21fdb71114be07434c0cc7bf63f6e1da274edebfe76f65fbd51ad2f14898b95b");
+}
+
+#[test]
+fn hash_384_string() {
+    compare_hash_384(b"Hello, world!", "55bc556b0d2fe0fce582ba5fe07baafff035653638c7ac0d5494c2a64c0bea1cc57331c7c12a45cdbca7f4c34a089eeb");
+}
+
+#[test]
+fn hash_512_none() {
+    compare_hash_512(b"", "cf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce47d0d13c5d85f2b0ff8318d2877eec2f63b931bd47417a81a538327af927da3e");
+}
+
+#[test]
+fn hash_512_string() {
+    compare_hash_512(b"Hello, world!", "c1527cd893c124773d811911970c8fe6e857d6df5dc9226bd8a160614c0cd963a4ddea2b94bb7d36021ef9d865d5cea294a82dd49a0bb269f51f6e7a57f79421");
+}
+
+#[test]
+fn hash_512_256_none() {
+    compare_hash_512_256(b"", "c672b8d1ef56ed28ab87c3622c5114069bdd3ad7b8f9737498d0c01ecef0967a");
+}
+
+#[test]
+fn hash_512_256_string() {
+    compare_hash_512_256(b"Hello, world!", "330c723f25267587db0b9f493463e017011239169cb57a6db216c63774367115");
+}
+
+// A minimal, test-only stand-in for the padding `Context::update`/
+// `finalize_words` apply internally, so the tests below can drive
+// `Sha256::compress` one block at a time without going through `new`.
+fn padded_blocks(inp: &[u8]) -> Vec<[u32; 16]> {
+    let bit_len = inp.len() as u64 * 8;
+    let padded_len = (inp.len() + 9).div_ceil(64) * 64;
+
+    let mut bytes = vec![0u8; padded_len];
+    bytes[..inp.len()].copy_from_slice(inp);
+    bytes[inp.len()] = 0x80;
+    bytes[padded_len - 8..].copy_from_slice(&bit_len.to_be_bytes());
+
+    bytes.chunks(64).map(|block| {
+        let mut words = [0u32; 16];
+        for (i, word) in words.iter_mut().enumerate() {
+            *word = u32::from_be_bytes(block[i * 4..i * 4 + 4].try_into().unwrap());
+        }
+        words
+    }).collect()
+}
+
+#[test]
+fn compress_matches_new() {
+    let mut state = Sha256::initial_state();
+
+    for block in padded_blocks(b"Hello, world!") {
+        Sha256::compress(&mut state, &block);
+    }
+
+    let expected = match Sha256::new(b"Hello, world!") {
+        Ok(res) => res,
+        Err(_) => panic!("Error: input size greater than 2^64 bits large.")
+    };
+    assert_eq!(state, [
+        u32::from_be_bytes(expected.as_bytes()[0..4].try_into().unwrap()),
+        u32::from_be_bytes(expected.as_bytes()[4..8].try_into().unwrap()),
+        u32::from_be_bytes(expected.as_bytes()[8..12].try_into().unwrap()),
+        u32::from_be_bytes(expected.as_bytes()[12..16].try_into().unwrap()),
+        u32::from_be_bytes(expected.as_bytes()[16..20].try_into().unwrap()),
+        u32::from_be_bytes(expected.as_bytes()[20..24].try_into().unwrap()),
+        u32::from_be_bytes(expected.as_bytes()[24..28].try_into().unwrap()),
+        u32::from_be_bytes(expected.as_bytes()[28..32].try_into().unwrap()),
+    ]);
+}
+
+// The 64-bit engine shares its streaming buffer logic with the 32-bit
+// one via `sha2_engine!`, but is exercised here too since it has its
+// own `Context` instantiation.
+#[test]
+fn sha512_update_appends_across_calls() {
+    let mut ctx = Sha512::context();
+    match ctx.update(b"He") {
+        Ok(()) => (),
+        Err(HashError::DataTooLarge) => panic!("Error: input size greater than 2^128 bits large.")
+    }
+    match ctx.update(b"llo, world!") {
+        Ok(()) => (),
+        Err(HashError::DataTooLarge) => panic!("Error: input size greater than 2^128 bits large.")
+    }
+
+    assert_eq!(ctx.finalize().to_string(), "c1527cd893c124773d811911970c8fe6e857d6df5dc9226bd8a160614c0cd963a4ddea2b94bb7d36021ef9d865d5cea294a82dd49a0bb269f51f6e7a57f79421");
+}
+
+fn compare_hmac(key: &[u8], msg: &[u8], other_hash: &str) {
+    let mac = match hmac_sha256(key, msg) {
+        Ok(mac) => mac,
+        Err(HashError::DataTooLarge) => panic!("Error: input size greater than 2^64 bits large.")
+    };
+
+    let mut hex = String::new();
+    for byte in mac.iter() {
+        hex.push_str(&format!("{:02x}", byte));
+    }
+    assert_eq!(hex, other_hash);
+}
+
+// RFC 4231 test vectors
+#[test]
+fn hmac_sha256_case_1() {
+    compare_hmac(&[0x0b; 20], b"Hi There",
+        "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7");
+}
+
+#[test]
+fn hmac_sha256_case_2() {
+    compare_hmac(b"Jefe", b"what do ya want for nothing?",
+        "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843");
+}
+
+#[test]
+fn hmac_sha256_case_3() {
+    compare_hmac(&[0xaa; 20], &[0xdd; 50],
+        "773ea91e36800e46854db8ebd09181a72959098b3ef8c122d9635514ced565fe");
+}
+
+#[test]
+fn hmac_sha256_case_6_key_longer_than_block() {
+    compare_hmac(&[0xaa; 131], b"Test Using Larger Than Block-Size Key - Hash Key First",
+        "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54");
+}
+
+// The accelerated x86 backend (when the running CPU has it) must
+// agree with the portable scalar loop on every block, not just whole
+// messages -- run both over a handful of blocks and compare.
+#[test]
+fn block_hash_dispatch_matches_scalar() {
+    let inputs: [&[u8]; 4] = [
+        b"",
+        b"Hello, world!",
+        b"The quick brown fox jumps over the lazy dog",
+        &[0x5au8; 200],
+    ];
+
+    for inp in inputs.iter() {
+        for block in padded_blocks(inp) {
+            let mut accelerated = INITIAL_HASH;
+            let mut scalar = INITIAL_HASH;
+
+            block_hash(&mut accelerated, &block);
+            block_hash_scalar(&mut scalar, &block);
+
+            assert_eq!(accelerated, scalar);
+        }
+    }
+}
+
+// Off-by-one padding bugs historically show up right at the block
+// boundary, where the trailing '1' bit and 64 bit length either fit
+// in the input's own block or spill into a fresh one.
+fn boundary_message(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i % 251) as u8).collect()
+}
+
+#[test]
+fn hash_boundary_55() {
+    compare_hash(&boundary_message(55), "463eb28e72f82e0a96c0a4cc53690c571281131f672aa229e0d45ae59b598b59");
+}
+
+#[test]
+fn hash_boundary_56() {
+    compare_hash(&boundary_message(56), "da2ae4d6b36748f2a318f23e7ab1dfdf45acdc9d049bd80e59de82a60895f562");
+}
+
+#[test]
+fn hash_boundary_63() {
+    compare_hash(&boundary_message(63), "29af2686fd53374a36b0846694cc342177e428d1647515f078784d69cdb9e488");
+}
+
+#[test]
+fn hash_boundary_64() {
+    compare_hash(&boundary_message(64), "fdeab9acf3710362bd2658cdc9a29e8f9c757fcf9811603a8c447cd1d9151108");
+}
+
+#[test]
+fn hash_boundary_119() {
+    compare_hash(&boundary_message(119), "da18797ed7c3a777f0847f429724a2d8cd5138e6ed2895c3fa1a6d39d18f7ec6");
+}
+
+#[test]
+fn hash_boundary_120() {
+    compare_hash(&boundary_message(120), "f52b23db1fbb6ded89ef42a23ce0c8922c45f25c50b568a93bf1c075420bbb7c");
+}
+
 #[test]
 fn hash_file() {
     use std::fs::read;